@@ -1,30 +1,89 @@
+use crate::error::WorkerError;
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize)]
 pub struct Config {
     pub directories: Vec<DirectoryEntry>,
     pub backup_root_path: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize)]
 pub struct DirectoryEntry {
     pub path: String,
     #[serde(default)]
     pub include_directories: bool,
     pub action: DirectoryAction,
+    #[serde(default)]
+    pub compression: Compression,
+    pub compression_level: Option<i64>,
+    /// Zip archive to extract from, required when `action` is `restore`.
+    pub restore_archive: Option<String>,
+    /// Caps on a restore's running uncompressed byte total / entry count, to
+    /// defend against zip bombs. Defaults apply when left unset.
+    pub max_unpacked_size: Option<u64>,
+    pub max_unpacked_count: Option<u64>,
+    /// Glob (or `regex:`-prefixed regular expression) patterns; only paths
+    /// matching at least one are acted on. Empty means "match all". Used by
+    /// the `list`, `clean`, `analyze` and `backup` actions; `restore` and
+    /// `dedup` do not filter.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Patterns excluded even if they match `include`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// For `action: clean`, only remove files whose mtime is older than this
+    /// many days. Unset removes every matching file, as before. Subdirectories
+    /// removed under `include_directories` are removed whole, whatever the age
+    /// of the files in them.
+    pub older_than_days: Option<u64>,
+    /// For `action: backup`, after writing the new archive, keep only the
+    /// `keep_last` newest `"{dir_name}_*.zip"` backups in `backup_root_path`
+    /// and delete the rest.
+    pub keep_last: Option<usize>,
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
+/// Declaration order doubles as the execution order `run_worker` sorts by
+/// for entries sharing a path: `Backup` must sort before `Clean` so a
+/// directory is archived before any retention cleanup can remove files
+/// from it.
+#[derive(Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum DirectoryAction {
+    Backup,
     Clean,
     List,
     Analyze,
-    Backup,
+    Restore,
+    Dedup,
+}
+
+/// Compression method applied when writing a backup zip archive.
+///
+/// Maps directly onto `zip::CompressionMethod`; `Stored` is the default so
+/// existing configs keep producing uncompressed archives unless they opt in.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    #[default]
+    Stored,
+    Deflated,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    pub fn to_zip_method(self) -> zip::CompressionMethod {
+        match self {
+            Compression::Stored => zip::CompressionMethod::Stored,
+            Compression::Deflated => zip::CompressionMethod::Deflated,
+            Compression::Bzip2 => zip::CompressionMethod::Bzip2,
+            Compression::Zstd => zip::CompressionMethod::Zstd,
+        }
+    }
 }
 
-pub fn parse_config(config_data: String) -> Config {
-    serde_json::from_str(&config_data).expect("Failed to parse config file")
+pub fn parse_config(config_data: String) -> Result<Config, WorkerError> {
+    Ok(serde_json::from_str(&config_data)?)
 }
 
 #[cfg(test)]
@@ -51,7 +110,7 @@ mod tests {
         }
         "#;
 
-        let config = parse_config(json_data.to_string());
+        let config = parse_config(json_data.to_string()).unwrap();
 
         assert_eq!(config.directories.len(), 2);
         assert_eq!(config.backup_root_path, Some("/backup".to_string()));
@@ -80,7 +139,7 @@ mod tests {
         }
         "#;
 
-        let config = parse_config(json_data.to_string());
+        let config = parse_config(json_data.to_string()).unwrap();
 
         assert_eq!(config.directories.len(), 1);
 
@@ -91,7 +150,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "unknown variant `wrong_action`")]
     fn test_parse_config_wrong_action_error() {
         let json_data = r#"
         {
@@ -104,11 +162,11 @@ mod tests {
         }
         "#;
 
-        parse_config(json_data.to_string());
+        let err = parse_config(json_data.to_string()).unwrap_err();
+        assert!(err.to_string().contains("unknown variant `wrong_action`"));
     }
 
     #[test]
-    #[should_panic(expected = "missing field `path`")]
     fn test_parse_config_missing_path_error() {
         let json_data = r#"
         {
@@ -120,6 +178,7 @@ mod tests {
         }
         "#;
 
-        parse_config(json_data.to_string());
+        let err = parse_config(json_data.to_string()).unwrap_err();
+        assert!(err.to_string().contains("missing field `path`"));
     }
 }
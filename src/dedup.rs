@@ -0,0 +1,150 @@
+use crate::error::WorkerError;
+use siphasher::sip128::{Hasher128, SipHasher13};
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::Read;
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+/// Number of leading bytes hashed during the cheap partial-hash pass. Files
+/// whose size or partial hash is unique never need their full contents read.
+const BLOCK_SIZE: usize = 4096;
+
+/// A set of files with identical contents, as found by [`find_duplicates`].
+pub struct DuplicateSet {
+    pub size: u64,
+    pub paths: Vec<PathBuf>,
+}
+
+impl DuplicateSet {
+    /// Bytes that could be reclaimed by keeping a single copy of this set.
+    pub fn wasted_space(&self) -> u64 {
+        self.size * (self.paths.len() as u64 - 1)
+    }
+}
+
+/// Finds groups of identical files under `root` in stages, to avoid hashing
+/// more data than necessary: bucket by file length first (a unique size can
+/// never collide), then by a 128-bit SipHash over only the first
+/// `BLOCK_SIZE` bytes, and only hash whole files that are still ambiguous
+/// after that. This avoids reading large files fully unless their size and
+/// partial hash already collide with another file's.
+pub fn find_duplicates(root: &str) -> Result<Vec<DuplicateSet>, WorkerError> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for entry in WalkDir::new(root) {
+        let entry = entry.map_err(|e| {
+            e.into_io_error()
+                .unwrap_or_else(|| std::io::Error::other("walk error"))
+        })?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let metadata = entry.metadata().map_err(|e| {
+            e.into_io_error()
+                .unwrap_or_else(|| std::io::Error::other("walk error"))
+        })?;
+        by_size.entry(metadata.len()).or_default().push(path.to_path_buf());
+    }
+
+    let mut duplicate_sets = Vec::new();
+
+    for (size, paths) in by_size {
+        if paths.len() < 2 {
+            continue;
+        }
+
+        let mut by_partial_hash: HashMap<u128, Vec<PathBuf>> = HashMap::new();
+        for path in paths {
+            let hash = hash_prefix(&path, BLOCK_SIZE)?;
+            by_partial_hash.entry(hash).or_default().push(path);
+        }
+
+        for candidates in by_partial_hash.into_values() {
+            if candidates.len() < 2 {
+                continue;
+            }
+
+            let mut by_full_hash: HashMap<u128, Vec<PathBuf>> = HashMap::new();
+            for path in candidates {
+                let hash = hash_prefix(&path, usize::MAX)?;
+                by_full_hash.entry(hash).or_default().push(path);
+            }
+
+            for group in by_full_hash.into_values() {
+                if group.len() > 1 {
+                    duplicate_sets.push(DuplicateSet { size, paths: group });
+                }
+            }
+        }
+    }
+
+    Ok(duplicate_sets)
+}
+
+/// Hashes up to `max_bytes` of `path` with a 128-bit SipHash, reading in
+/// `BLOCK_SIZE` chunks so the full-file pass doesn't need its own buffering.
+fn hash_prefix(path: &PathBuf, max_bytes: usize) -> Result<u128, WorkerError> {
+    let mut file = File::open(path)?;
+    let mut hasher = SipHasher13::new();
+    let mut buffer = [0u8; BLOCK_SIZE];
+    let mut remaining = max_bytes;
+
+    while remaining > 0 {
+        let chunk = buffer.len().min(remaining);
+        let bytes_read = file.read(&mut buffer[..chunk])?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.write(&buffer[..bytes_read]);
+        remaining -= bytes_read;
+    }
+
+    Ok(hasher.finish128().as_u128())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_duplicates_detects_identical_files() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), b"duplicate content").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), b"duplicate content").unwrap();
+        fs::write(temp_dir.path().join("c.txt"), b"unique content").unwrap();
+
+        let duplicate_sets = find_duplicates(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(duplicate_sets.len(), 1);
+        assert_eq!(duplicate_sets[0].paths.len(), 2);
+        assert_eq!(
+            duplicate_sets[0].wasted_space(),
+            "duplicate content".len() as u64
+        );
+    }
+
+    #[test]
+    fn test_find_duplicates_ignores_unique_sizes() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("a.txt"), b"short").unwrap();
+        fs::write(temp_dir.path().join("b.txt"), b"much longer content").unwrap();
+
+        let duplicate_sets = find_duplicates(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert!(duplicate_sets.is_empty());
+    }
+
+    #[test]
+    fn test_find_duplicates_missing_root_errors() {
+        let temp_dir = tempdir().unwrap();
+        let missing_dir = temp_dir.path().join("does-not-exist");
+
+        let result = find_duplicates(missing_dir.to_str().unwrap());
+
+        assert!(matches!(result, Err(WorkerError::Io(_))));
+    }
+}
@@ -0,0 +1,41 @@
+use thiserror::Error;
+
+/// Errors surfaced by the worker's config parsing and directory actions.
+/// Replaces the `expect`/`panic!` calls that used to abort the whole run on
+/// the first bad directory, so `run_worker` can keep going and callers that
+/// use this crate as a library get a `Result` instead of a crash.
+#[derive(Debug, Error)]
+pub enum WorkerError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse config: {0}")]
+    Config(#[from] serde_json::Error),
+
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Invalid filter pattern '{0}': {1}")]
+    InvalidFilter(String, String),
+
+    #[error("Backup requires 'backup_root_path' in config (directory '{0}')")]
+    BackupPathMissing(String),
+
+    #[error("Cannot derive a backup archive name from directory path '{0}'")]
+    BackupNameInvalid(String),
+
+    #[error("Restore requires 'restore_archive' in directory entry (directory '{0}')")]
+    RestoreArchiveMissing(String),
+
+    #[error("Refusing to restore unsafe archive entry '{0}' from '{1}'")]
+    PathTraversal(String, String),
+
+    #[error("Archive '{0}' exceeds max_unpacked_size ({1} bytes)")]
+    UnpackedSizeExceeded(String, u64),
+
+    #[error("Archive '{0}' exceeds max_unpacked_count ({1})")]
+    UnpackedCountExceeded(String, u64),
+
+    #[error("{0} of {1} directory entries failed")]
+    Failures(usize, usize),
+}
@@ -0,0 +1,113 @@
+use crate::error::WorkerError;
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use std::path::Path;
+
+/// A single compiled include/exclude pattern. Patterns are glob syntax by
+/// default (e.g. `*.tmp`, `logs/**/*.log`); prefixing one with `regex:`
+/// compiles the remainder as a regular expression instead, so a single
+/// pattern list can mix both styles.
+#[derive(Debug)]
+enum CompiledPattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl CompiledPattern {
+    fn compile(pattern: &str) -> Result<CompiledPattern, WorkerError> {
+        match pattern.strip_prefix("regex:") {
+            Some(regex_pattern) => {
+                let regex = Regex::new(regex_pattern)
+                    .map_err(|e| WorkerError::InvalidFilter(pattern.to_string(), e.to_string()))?;
+                Ok(CompiledPattern::Regex(regex))
+            }
+            None => {
+                let matcher = Glob::new(pattern)
+                    .map_err(|e| WorkerError::InvalidFilter(pattern.to_string(), e.to_string()))?
+                    .compile_matcher();
+                Ok(CompiledPattern::Glob(matcher))
+            }
+        }
+    }
+
+    fn is_match(&self, candidate: &str) -> bool {
+        match self {
+            CompiledPattern::Glob(matcher) => matcher.is_match(candidate),
+            CompiledPattern::Regex(regex) => regex.is_match(candidate),
+        }
+    }
+}
+
+/// Shared include/exclude filtering consulted by `list_directory`,
+/// `clean_directory`, `analyze_directory` and `backup_directory` before
+/// acting on a path. Patterns match against the path relative to the
+/// configured directory root; an empty include list means "match all".
+#[derive(Debug)]
+pub struct PathFilter {
+    include: Vec<CompiledPattern>,
+    exclude: Vec<CompiledPattern>,
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<PathFilter, WorkerError> {
+        Ok(PathFilter {
+            include: include
+                .iter()
+                .map(|p| CompiledPattern::compile(p))
+                .collect::<Result<_, _>>()?,
+            exclude: exclude
+                .iter()
+                .map(|p| CompiledPattern::compile(p))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Whether `relative_path` (relative to the directory entry's root)
+    /// should be acted on.
+    pub fn matches(&self, relative_path: &Path) -> bool {
+        let candidate = relative_path.to_string_lossy().replace('\\', "/");
+
+        let included = self.include.is_empty() || self.include.iter().any(|p| p.is_match(&candidate));
+        let excluded = self.exclude.iter().any(|p| p.is_match(&candidate));
+
+        included && !excluded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_include_matches_everything() {
+        let filter = PathFilter::new(&[], &[]).unwrap();
+        assert!(filter.matches(Path::new("anything.txt")));
+    }
+
+    #[test]
+    fn test_glob_include_restricts_matches() {
+        let filter = PathFilter::new(&["*.tmp".to_string()], &[]).unwrap();
+        assert!(filter.matches(Path::new("cache.tmp")));
+        assert!(!filter.matches(Path::new("cache.log")));
+    }
+
+    #[test]
+    fn test_exclude_overrides_include() {
+        let filter = PathFilter::new(&["*".to_string()], &["*.log".to_string()]).unwrap();
+        assert!(filter.matches(Path::new("cache.tmp")));
+        assert!(!filter.matches(Path::new("cache.log")));
+    }
+
+    #[test]
+    fn test_regex_prefix_uses_regex_matching() {
+        let filter = PathFilter::new(&["regex:^backup_\\d+\\.zip$".to_string()], &[]).unwrap();
+        assert!(filter.matches(Path::new("backup_20250101.zip")));
+        assert!(!filter.matches(Path::new("backup_latest.zip")));
+    }
+
+    #[test]
+    fn test_invalid_regex_pattern_is_rejected() {
+        let err = PathFilter::new(&["regex:(".to_string()], &[]).unwrap_err();
+        assert!(matches!(err, WorkerError::InvalidFilter(_, _)));
+    }
+}
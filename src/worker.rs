@@ -1,166 +1,487 @@
-use crate::config::{parse_config, DirectoryAction, DirectoryEntry};
-use chrono::Utc;
+use crate::config::{parse_config, Compression, DirectoryAction, DirectoryEntry};
+use crate::dedup::find_duplicates;
+use crate::error::WorkerError;
+use crate::filter::PathFilter;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use std::fs;
 use std::fs::File;
 use std::io::{BufWriter, Read, Write};
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
+use zip::read::ZipArchive;
 use zip::write::SimpleFileOptions;
 
+/// Default caps applied to `restore_directory` when a `DirectoryEntry` does
+/// not set `max_unpacked_size`/`max_unpacked_count`. These bound the total
+/// uncompressed bytes and entry count an extraction will accept before
+/// aborting, so a malicious or corrupt archive can't exhaust disk space.
+const DEFAULT_MAX_UNPACKED_SIZE: u64 = 10 * 1024 * 1024 * 1024;
+const DEFAULT_MAX_UNPACKED_COUNT: u64 = 100_000;
+
 impl DirectoryAction {
-    fn execute(&self, entry: &DirectoryEntry, timestamp: &str, backup_root_path: Option<&str>) {
+    fn execute(
+        &self,
+        entry: &DirectoryEntry,
+        timestamp: &str,
+        backup_root_path: Option<&str>,
+    ) -> Result<(), WorkerError> {
+        let filter = PathFilter::new(&entry.include, &entry.exclude)?;
         match self {
-            DirectoryAction::List => list_directory(&entry.path, entry.include_directories),
-            DirectoryAction::Clean => clean_directory(&entry.path, entry.include_directories),
-            DirectoryAction::Analyze => analyze_directory(&entry.path, entry.include_directories),
+            DirectoryAction::List => list_directory(&entry.path, entry.include_directories, &filter),
+            DirectoryAction::Clean => clean_directory(
+                &entry.path,
+                entry.include_directories,
+                &filter,
+                entry.older_than_days,
+            ),
+            DirectoryAction::Analyze => {
+                analyze_directory(&entry.path, entry.include_directories, &filter)
+            }
             DirectoryAction::Backup => {
-                if let Some(backup_root_path) = backup_root_path {
-                    backup_directory(&entry.path, timestamp, backup_root_path);
-                } else {
-                    panic!("Backup requires 'backup_root_path' in config.");
-                }
+                let backup_root_path = backup_root_path
+                    .ok_or_else(|| WorkerError::BackupPathMissing(entry.path.clone()))?;
+                backup_directory(
+                    &entry.path,
+                    timestamp,
+                    backup_root_path,
+                    entry.compression,
+                    entry.compression_level,
+                    &filter,
+                )
+            }
+            DirectoryAction::Restore => {
+                let restore_archive = entry
+                    .restore_archive
+                    .as_deref()
+                    .ok_or_else(|| WorkerError::RestoreArchiveMissing(entry.path.clone()))?;
+                restore_directory(
+                    restore_archive,
+                    &entry.path,
+                    entry.max_unpacked_size,
+                    entry.max_unpacked_count,
+                )
             }
+            DirectoryAction::Dedup => dedup_directory(&entry.path),
         }
     }
 }
 
-pub fn run_worker(config_path: &str) {
+/// Runs every directory entry in `config_path`, processing each
+/// independently: a failing entry is reported and skipped rather than
+/// aborting the remaining entries. Returns `Err(WorkerError::Failures)` if
+/// any entry failed, so callers get a nonzero summary at the end.
+pub fn run_worker(config_path: &str) -> Result<(), WorkerError> {
     let timestamp = Utc::now().format("%Y%m%d%H%M%S").to_string();
-    let config_data = fs::read_to_string(config_path).expect("Failed to read config file");
-    let config = parse_config(config_data);
+    let config_data = fs::read_to_string(config_path)?;
+    let config = parse_config(config_data)?;
 
     // sort directory entry  by path and action (so backup is always before clean)
     let mut sorted_entries: Vec<_> = config.directories.iter().collect();
     sorted_entries.sort_by(|a, b| a.path.cmp(&b.path).then(a.action.cmp(&b.action)));
 
+    let total = sorted_entries.len();
+    let mut failures = 0;
+
     for entry in sorted_entries {
-        entry
+        let result = entry
             .action
             .execute(entry, &timestamp, config.backup_root_path.as_deref());
+
+        let result = result.and_then(|()| {
+            if entry.action == DirectoryAction::Backup {
+                if let (Some(keep_last), Some(backup_root_path)) =
+                    (entry.keep_last, config.backup_root_path.as_deref())
+                {
+                    let dir_name = Path::new(&entry.path)
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .unwrap_or(&entry.path);
+                    return rotate_backups(backup_root_path, dir_name, keep_last);
+                }
+            }
+            Ok(())
+        });
+
+        if let Err(err) = result {
+            eprintln!("Error processing '{}': {}", entry.path, err);
+            failures += 1;
+        }
     }
+
+    if failures > 0 {
+        return Err(WorkerError::Failures(failures, total));
+    }
+
+    Ok(())
 }
 
-fn analyze_directory(path: &str, include_directories: bool) {
+fn analyze_directory(
+    path: &str,
+    include_directories: bool,
+    filter: &PathFilter,
+) -> Result<(), WorkerError> {
     println!(
         "Analyzing directory (subdirs={}): '{}'",
         include_directories, path
     );
+    let root = Path::new(path);
     let (file_count, total_size) =
-        analyze_directory_recursive(Path::new(path), Some(include_directories));
+        analyze_directory_recursive(root, root, include_directories, filter)?;
 
     println!("Number of files: {}", file_count);
     println!("Total size: {} bytes", total_size);
     println!();
+    Ok(())
 }
 
-fn analyze_directory_recursive(path: &Path, include_directories: Option<bool>) -> (u64, u64) {
-    let include_directories = include_directories.unwrap_or(false);
-    let dir_entries = fs::read_dir(path).expect("Failed to read directory");
+fn analyze_directory_recursive(
+    root: &Path,
+    path: &Path,
+    include_directories: bool,
+    filter: &PathFilter,
+) -> Result<(u64, u64), WorkerError> {
+    let dir_entries = fs::read_dir(path)?;
 
     let mut file_count = 0;
     let mut total_size = 0;
 
     for entry in dir_entries {
-        let entry = entry.expect("Failed to read entry");
+        let entry = entry?;
         let path = entry.path();
+        let relative_path = path.strip_prefix(root).unwrap_or(&path);
+        if !filter.matches(relative_path) {
+            continue;
+        }
+
         if path.is_dir() {
             if include_directories {
-                let dir_stats = analyze_directory_recursive(&path, Some(include_directories));
+                let dir_stats =
+                    analyze_directory_recursive(root, &path, include_directories, filter)?;
                 file_count += dir_stats.0;
                 total_size += dir_stats.1;
             }
         } else {
             file_count += 1;
-            total_size += fs::metadata(&path).expect("Failed to read metadata").len();
+            total_size += fs::metadata(&path)?.len();
+        }
+    }
+
+    Ok((file_count, total_size))
+}
+
+fn dedup_directory(path: &str) -> Result<(), WorkerError> {
+    println!("Scanning for duplicate files: '{}'", path);
+
+    let duplicate_sets = find_duplicates(path)?;
+    let mut wasted_space = 0;
+
+    for duplicate_set in &duplicate_sets {
+        println!(
+            "Duplicate set ({} bytes each, {} wasted):",
+            duplicate_set.size,
+            duplicate_set.wasted_space()
+        );
+        for duplicate_path in &duplicate_set.paths {
+            println!("  {}", duplicate_path.display());
         }
+        wasted_space += duplicate_set.wasted_space();
     }
 
-    (file_count, total_size)
+    println!("Duplicate sets found: {}", duplicate_sets.len());
+    println!("Reclaimable space: {} bytes", wasted_space);
+    println!();
+    Ok(())
 }
 
-fn list_directory(path: &str, include_directories: bool) {
+fn list_directory(
+    path: &str,
+    include_directories: bool,
+    filter: &PathFilter,
+) -> Result<(), WorkerError> {
     println!(
         "Listing directory (subdirs={}): '{}'",
         include_directories, path
     );
-    let dir_entries = fs::read_dir(path).expect("Failed to read directory");
-
-    for entry in dir_entries {
-        let entry = entry.expect("Failed to read entry");
-        let path = entry.path();
-        if path.is_dir() {
-            if include_directories {
-                println!("Directory: '{}'", path.display());
-            }
+    for entry_path in list_matching_entries(path, include_directories, filter)? {
+        if entry_path.is_dir() {
+            println!("Directory: '{}'", entry_path.display());
         } else {
-            println!("File: '{}'", path.display());
+            println!("File: '{}'", entry_path.display());
         }
     }
     println!();
+    Ok(())
+}
+
+/// Directory entries directly under `path` that pass `filter`, with
+/// directories dropped unless `include_directories` is set.
+fn list_matching_entries(
+    path: &str,
+    include_directories: bool,
+    filter: &PathFilter,
+) -> Result<Vec<PathBuf>, WorkerError> {
+    let root = Path::new(path);
+    let mut matches = Vec::new();
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let entry_path = entry.path();
+        let relative_path = entry_path.strip_prefix(root).unwrap_or(&entry_path);
+        if !filter.matches(relative_path) {
+            continue;
+        }
+        if entry_path.is_dir() && !include_directories {
+            continue;
+        }
+        matches.push(entry_path);
+    }
+
+    Ok(matches)
 }
 
-fn clean_directory(path: &str, include_directories: bool) {
+fn clean_directory(
+    path: &str,
+    include_directories: bool,
+    filter: &PathFilter,
+    older_than_days: Option<u64>,
+) -> Result<(), WorkerError> {
     println!(
         "Cleaning directory (subdirs={}): '{}'",
         include_directories, path
     );
-    let dir_entries = fs::read_dir(path).expect("Failed to read directory");
+    let root = Path::new(path);
+    let dir_entries = fs::read_dir(path)?;
 
     for entry in dir_entries {
-        let entry = entry.expect("Failed to read entry");
+        let entry = entry?;
         let path = entry.path();
+        let relative_path = path.strip_prefix(root).unwrap_or(&path);
+        if !filter.matches(relative_path) {
+            continue;
+        }
+
         if path.is_dir() {
             if include_directories {
                 println!("Removing directory: '{}'", path.display());
-                fs::remove_dir_all(path).expect("Failed to remove directory");
+                fs::remove_dir_all(path)?;
             } else {
                 println!("Skipping directory: '{}'", path.display());
             }
         } else {
+            if let Some(older_than_days) = older_than_days {
+                let modified = fs::metadata(&path)?.modified()?;
+                if !is_older_than_days(modified, older_than_days) {
+                    println!("Skipping file (too recent): '{}'", path.display());
+                    continue;
+                }
+            }
             println!("Removing file: '{}'", path.display());
-            fs::remove_file(path).expect("Failed to remove file");
+            fs::remove_file(path)?;
         }
     }
-    println!()
+    println!();
+    Ok(())
+}
+
+/// Whether `modified` is more than `days` days in the past.
+fn is_older_than_days(modified: std::time::SystemTime, days: u64) -> bool {
+    let modified: DateTime<Utc> = modified.into();
+    Utc::now().signed_duration_since(modified) > chrono::Duration::days(days as i64)
+}
+
+/// Keeps only the `keep_last` newest `"{dir_name}_*.zip"` backups in
+/// `backup_root_path`, parsing the `%Y%m%d%H%M%S` suffix the worker stamps
+/// backups with, and deletes the rest.
+fn rotate_backups(
+    backup_root_path: &str,
+    dir_name: &str,
+    keep_last: usize,
+) -> Result<(), WorkerError> {
+    let prefix = format!("{}_", dir_name);
+    let mut backups: Vec<(NaiveDateTime, PathBuf)> = Vec::new();
+
+    for entry in fs::read_dir(backup_root_path)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        let Some(rest) = file_name.strip_prefix(&prefix) else {
+            continue;
+        };
+        let Some(timestamp) = rest.strip_suffix(".zip") else {
+            continue;
+        };
+        let Ok(parsed) = NaiveDateTime::parse_from_str(timestamp, "%Y%m%d%H%M%S") else {
+            continue;
+        };
+
+        backups.push((parsed, entry.path()));
+    }
+
+    backups.sort_by_key(|b| std::cmp::Reverse(b.0));
+
+    for (_, path) in backups.into_iter().skip(keep_last) {
+        println!("Pruning old backup: '{}'", path.display());
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
 }
 
-fn backup_directory(path: &str, timestamp: &str, backup_root_path: &str) {
+fn backup_directory(
+    path: &str,
+    timestamp: &str,
+    backup_root_path: &str,
+    compression: Compression,
+    compression_level: Option<i64>,
+    filter: &PathFilter,
+) -> Result<(), WorkerError> {
     let src_dir = Path::new(path);
-    let dir_name = src_dir.file_name().unwrap().to_str().unwrap();
+    fs::metadata(src_dir)?;
+    let dir_name = src_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| WorkerError::BackupNameInvalid(path.to_string()))?;
     let zip_file_name = format!("{}/{}_{}.zip", backup_root_path, dir_name, timestamp);
     println!("Creating backup of '{}' -> '{}'", path, zip_file_name);
 
-    fs::create_dir_all(backup_root_path).expect("Failed to create backup root directory");
-    let file = File::create(&zip_file_name).expect("Failed to create zip file");
+    fs::create_dir_all(backup_root_path)?;
+    let file = File::create(&zip_file_name)?;
 
     let writer = BufWriter::new(file);
     let mut zip = zip::ZipWriter::new(writer);
-    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
-
-    for entry in WalkDir::new(&src_dir).into_iter().filter_map(|e| e.ok()) {
+    let options = SimpleFileOptions::default()
+        .compression_method(compression.to_zip_method())
+        .compression_level(compression_level);
+
+    for entry in WalkDir::new(src_dir) {
+        let entry = entry.map_err(|e| {
+            e.into_io_error()
+                .unwrap_or_else(|| std::io::Error::other("walk error"))
+        })?;
         let path = entry.path();
-        let name = path.strip_prefix(&src_dir).unwrap();
+        let name = path.strip_prefix(src_dir).unwrap();
+        if !filter.matches(name) {
+            continue;
+        }
+
         // When using windows sep, not all tools can deal with the subdirs
         let path_as_string = name.to_str().unwrap().replace("\\", "/");
         let mut buffer = Vec::new();
 
         if path.is_file() {
             println!("Adding file {}", path.display());
-            zip.start_file(path_as_string, options).unwrap();
-            let mut f = File::open(path).expect("Failed to open file");
+            zip.start_file(path_as_string, options)?;
+            let mut f = File::open(path)?;
 
-            f.read_to_end(&mut buffer).expect("Failed to read file");
-            zip.write_all(&buffer).unwrap();
+            f.read_to_end(&mut buffer)?;
+            zip.write_all(&buffer)?;
             buffer.clear();
         } else if !name.as_os_str().is_empty() {
             println!("Adding dir {}", path.display());
-            zip.add_directory(path_as_string, options).unwrap();
+            zip.add_directory(path_as_string, options)?;
         }
     }
 
-    zip.finish().unwrap();
+    zip.finish()?;
     println!("Backup created: '{}'", zip_file_name);
     println!();
+    Ok(())
+}
+
+/// Extracts `archive_path` into `dest_path`, rejecting entries that try to
+/// escape the destination and aborting once the archive's uncompressed size
+/// or entry count crosses the configured (or default) limits. Limits are
+/// enforced per entry while writing, so a bad archive is caught before it
+/// fills the disk rather than after extraction has already happened.
+fn restore_directory(
+    archive_path: &str,
+    dest_path: &str,
+    max_unpacked_size: Option<u64>,
+    max_unpacked_count: Option<u64>,
+) -> Result<(), WorkerError> {
+    let max_unpacked_size = max_unpacked_size.unwrap_or(DEFAULT_MAX_UNPACKED_SIZE);
+    let max_unpacked_count = max_unpacked_count.unwrap_or(DEFAULT_MAX_UNPACKED_COUNT);
+
+    println!("Restoring '{}' -> '{}'", archive_path, dest_path);
+
+    let file = File::open(archive_path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    fs::create_dir_all(dest_path)?;
+    let dest_dir = Path::new(dest_path).canonicalize()?;
+
+    let mut unpacked_size: u64 = 0;
+    let mut unpacked_count: u64 = 0;
+
+    for i in 0..archive.len() {
+        let mut zip_entry = archive.by_index(i)?;
+        let entry_name = zip_entry.name().to_string();
+        let relative_path = sanitize_archive_entry_path(&entry_name).ok_or_else(|| {
+            WorkerError::PathTraversal(entry_name.clone(), archive_path.to_string())
+        })?;
+
+        unpacked_count += 1;
+        if unpacked_count > max_unpacked_count {
+            return Err(WorkerError::UnpackedCountExceeded(
+                archive_path.to_string(),
+                max_unpacked_count,
+            ));
+        }
+
+        let out_path = dest_dir.join(&relative_path);
+
+        if zip_entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        println!("Restoring file '{}'", out_path.display());
+        let mut out_file = File::create(&out_path)?;
+
+        let mut buffer = [0u8; 8192];
+        loop {
+            let bytes_read = zip_entry.read(&mut buffer)?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            unpacked_size += bytes_read as u64;
+            if unpacked_size > max_unpacked_size {
+                return Err(WorkerError::UnpackedSizeExceeded(
+                    archive_path.to_string(),
+                    max_unpacked_size,
+                ));
+            }
+
+            out_file.write_all(&buffer[..bytes_read])?;
+        }
+    }
+
+    println!("Restore complete: '{}'", dest_path);
+    println!();
+    Ok(())
+}
+
+/// Normalizes a zip entry name into a path safe to join onto a destination
+/// directory, rejecting `..`, absolute paths, and drive-letter prefixes so a
+/// malicious archive cannot write outside of the restore target.
+fn sanitize_archive_entry_path(name: &str) -> Option<PathBuf> {
+    let mut sanitized = PathBuf::new();
+
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => sanitized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(sanitized)
 }
 
 #[cfg(test)]
@@ -198,7 +519,11 @@ mod tests {
             test_dir.to_str().unwrap(),
             timestamp,
             backup_root_path.to_str().unwrap(),
-        );
+            Compression::Stored,
+            None,
+            &PathFilter::new(&[], &[]).unwrap(),
+        )
+        .unwrap();
 
         let zip_file_name = backup_root_path.join(format!("dummy_{}.zip", timestamp));
         assert!(zip_file_name.exists(), "Backup zip file was not created");
@@ -220,4 +545,341 @@ mod tests {
             .expect("Failed to read file2.txt");
         assert_eq!(file2_content, "Hello, subdir!");
     }
+
+    #[test]
+    fn test_backup_directory_with_deflated_compression() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        let backup_root_path = temp_dir.path().join("backup");
+        let timestamp = "20250101121500";
+
+        create_test_files(&test_dir);
+
+        backup_directory(
+            test_dir.to_str().unwrap(),
+            timestamp,
+            backup_root_path.to_str().unwrap(),
+            Compression::Deflated,
+            Some(6),
+            &PathFilter::new(&[], &[]).unwrap(),
+        )
+        .unwrap();
+
+        let zip_file_name = backup_root_path.join(format!("dummy_{}.zip", timestamp));
+        let file = File::open(&zip_file_name).expect("Failed to open zip file");
+        let mut zip = ZipArchive::new(file).expect("Failed to read zip archive");
+
+        let entry = zip.by_name("file1.txt").expect("file1.txt not found in zip");
+        assert_eq!(entry.compression(), zip::CompressionMethod::Deflated);
+    }
+
+    #[test]
+    fn test_backup_directory_honors_include_filter() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        let backup_root_path = temp_dir.path().join("backup");
+        let timestamp = "20250101121500";
+
+        create_test_files(&test_dir);
+
+        backup_directory(
+            test_dir.to_str().unwrap(),
+            timestamp,
+            backup_root_path.to_str().unwrap(),
+            Compression::Stored,
+            None,
+            &PathFilter::new(&["file1.txt".to_string()], &[]).unwrap(),
+        )
+        .unwrap();
+
+        let zip_file_name = backup_root_path.join(format!("dummy_{}.zip", timestamp));
+        let file = File::open(&zip_file_name).expect("Failed to open zip file");
+        let mut zip = ZipArchive::new(file).expect("Failed to read zip archive");
+
+        assert!(zip.by_name("file1.txt").is_ok());
+        assert!(zip.by_name("subdir/file2.txt").is_err());
+    }
+
+    #[test]
+    fn test_list_directory_honors_include_filter() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        create_test_files(&test_dir);
+
+        let matches = list_matching_entries(
+            test_dir.to_str().unwrap(),
+            true,
+            &PathFilter::new(&["file1.txt".to_string()], &[]).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(matches, vec![test_dir.join("file1.txt")]);
+    }
+
+    #[test]
+    fn test_analyze_directory_honors_include_filter() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        create_test_files(&test_dir);
+
+        let (file_count, total_size) = analyze_directory_recursive(
+            &test_dir,
+            &test_dir,
+            true,
+            &PathFilter::new(&["file1.txt".to_string()], &[]).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(file_count, 1);
+        assert_eq!(total_size, "Hello, world!".len() as u64);
+    }
+
+    #[test]
+    fn test_clean_directory_honors_exclude_filter() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        create_test_files(&test_dir);
+
+        clean_directory(
+            test_dir.to_str().unwrap(),
+            true,
+            &PathFilter::new(&[], &["file1.txt".to_string()]).unwrap(),
+            None,
+        )
+        .unwrap();
+
+        assert!(test_dir.join("file1.txt").exists());
+        assert!(!test_dir.join("subdir").exists());
+    }
+
+    #[test]
+    fn test_backup_directory_missing_root_errors() {
+        let temp_dir = tempdir().unwrap();
+        let missing_dir = temp_dir.path().join("does-not-exist");
+        let backup_root_path = temp_dir.path().join("backup");
+
+        let result = backup_directory(
+            missing_dir.to_str().unwrap(),
+            "20250101121500",
+            backup_root_path.to_str().unwrap(),
+            Compression::Stored,
+            None,
+            &PathFilter::new(&[], &[]).unwrap(),
+        );
+
+        assert!(matches!(result, Err(WorkerError::Io(_))));
+    }
+
+    #[test]
+    fn test_backup_directory_rejects_nameless_path() {
+        let backup_root_path = tempdir().unwrap().path().join("backup");
+
+        let result = backup_directory(
+            "/",
+            "20250101121500",
+            backup_root_path.to_str().unwrap(),
+            Compression::Stored,
+            None,
+            &PathFilter::new(&[], &[]).unwrap(),
+        );
+
+        assert!(matches!(result, Err(WorkerError::BackupNameInvalid(_))));
+    }
+
+    #[test]
+    fn test_restore_directory_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        let backup_root_path = temp_dir.path().join("backup");
+        let restore_path = temp_dir.path().join("restored");
+        let timestamp = "20250101121500";
+
+        create_test_files(&test_dir);
+        backup_directory(
+            test_dir.to_str().unwrap(),
+            timestamp,
+            backup_root_path.to_str().unwrap(),
+            Compression::Stored,
+            None,
+            &PathFilter::new(&[], &[]).unwrap(),
+        )
+        .unwrap();
+        let zip_file_name = backup_root_path.join(format!("dummy_{}.zip", timestamp));
+
+        restore_directory(
+            zip_file_name.to_str().unwrap(),
+            restore_path.to_str().unwrap(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(restore_path.join("file1.txt")).unwrap(),
+            "Hello, world!"
+        );
+        assert_eq!(
+            fs::read_to_string(restore_path.join("subdir").join("file2.txt")).unwrap(),
+            "Hello, subdir!"
+        );
+    }
+
+    #[test]
+    fn test_restore_directory_rejects_path_traversal() {
+        let temp_dir = tempdir().unwrap();
+        let restore_path = temp_dir.path().join("restored");
+        let zip_file_name = temp_dir.path().join("malicious.zip");
+
+        let file = File::create(&zip_file_name).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        zip.start_file("../escaped.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"pwned").unwrap();
+        zip.finish().unwrap();
+
+        let result = restore_directory(
+            zip_file_name.to_str().unwrap(),
+            restore_path.to_str().unwrap(),
+            None,
+            None,
+        );
+
+        assert!(matches!(result, Err(WorkerError::PathTraversal(_, _))));
+    }
+
+    #[test]
+    fn test_restore_directory_enforces_max_unpacked_size() {
+        let temp_dir = tempdir().unwrap();
+        let restore_path = temp_dir.path().join("restored");
+        let zip_file_name = temp_dir.path().join("big.zip");
+
+        let file = File::create(&zip_file_name).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        zip.start_file("big.txt", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&[0u8; 1024]).unwrap();
+        zip.finish().unwrap();
+
+        let result = restore_directory(
+            zip_file_name.to_str().unwrap(),
+            restore_path.to_str().unwrap(),
+            Some(10),
+            None,
+        );
+
+        assert!(matches!(result, Err(WorkerError::UnpackedSizeExceeded(_, _))));
+    }
+
+    #[test]
+    fn test_run_worker_continues_past_a_failing_entry() {
+        let temp_dir = tempdir().unwrap();
+        let good_dir = temp_dir.path().join("good");
+        fs::create_dir_all(&good_dir).unwrap();
+        fs::write(good_dir.join("file.txt"), b"hello").unwrap();
+
+        let config_path = temp_dir.path().join("config.json");
+        let config_json = format!(
+            r#"{{
+                "directories": [
+                    {{"path": "{}", "action": "list"}},
+                    {{"path": "{}", "action": "list"}}
+                ]
+            }}"#,
+            good_dir.to_str().unwrap().replace('\\', "\\\\"),
+            temp_dir.path().join("missing").to_str().unwrap().replace('\\', "\\\\"),
+        );
+        fs::write(&config_path, config_json).unwrap();
+
+        let result = run_worker(config_path.to_str().unwrap());
+
+        assert!(matches!(result, Err(WorkerError::Failures(1, 2))));
+    }
+
+    #[test]
+    fn test_run_worker_backs_up_before_cleaning_same_path() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        let backup_root_path = temp_dir.path().join("backup");
+        fs::create_dir_all(&test_dir).unwrap();
+        fs::write(test_dir.join("file.txt"), b"hello").unwrap();
+
+        let config_path = temp_dir.path().join("config.json");
+        let config_json = format!(
+            r#"{{
+                "directories": [
+                    {{"path": "{path}", "action": "clean"}},
+                    {{"path": "{path}", "action": "backup"}}
+                ],
+                "backup_root_path": "{backup_root_path}"
+            }}"#,
+            path = test_dir.to_str().unwrap().replace('\\', "\\\\"),
+            backup_root_path = backup_root_path.to_str().unwrap().replace('\\', "\\\\"),
+        );
+        fs::write(&config_path, config_json).unwrap();
+
+        run_worker(config_path.to_str().unwrap()).unwrap();
+
+        assert!(
+            !test_dir.join("file.txt").exists(),
+            "clean should still have removed the now-backed-up file"
+        );
+
+        let zip_entries: Vec<_> = fs::read_dir(&backup_root_path)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        let zip_path = zip_entries
+            .into_iter()
+            .find(|p| p.extension().is_some_and(|ext| ext == "zip"))
+            .expect("backup zip was not created");
+        let file = File::open(zip_path).unwrap();
+        let mut zip = ZipArchive::new(file).unwrap();
+        assert!(
+            zip.by_name("file.txt").is_ok(),
+            "file.txt should have been archived before clean removed it"
+        );
+    }
+
+    #[test]
+    fn test_clean_directory_keeps_recent_files_when_older_than_days_is_set() {
+        let temp_dir = tempdir().unwrap();
+        let test_dir = temp_dir.path().join("dummy");
+        fs::create_dir_all(&test_dir).unwrap();
+        fs::write(test_dir.join("recent.txt"), b"recent").unwrap();
+
+        clean_directory(
+            test_dir.to_str().unwrap(),
+            false,
+            &PathFilter::new(&[], &[]).unwrap(),
+            Some(30),
+        )
+        .unwrap();
+
+        assert!(test_dir.join("recent.txt").exists());
+    }
+
+    #[test]
+    fn test_is_older_than_days() {
+        let now = std::time::SystemTime::now();
+        assert!(!is_older_than_days(now, 30));
+
+        let old = now - std::time::Duration::from_secs(31 * 24 * 60 * 60);
+        assert!(is_older_than_days(old, 30));
+    }
+
+    #[test]
+    fn test_rotate_backups_keeps_only_newest() {
+        let temp_dir = tempdir().unwrap();
+        let backup_root_path = temp_dir.path();
+
+        for timestamp in ["20240101000000", "20240601000000", "20250101000000"] {
+            File::create(backup_root_path.join(format!("dummy_{}.zip", timestamp))).unwrap();
+        }
+
+        rotate_backups(backup_root_path.to_str().unwrap(), "dummy", 2).unwrap();
+
+        assert!(!backup_root_path.join("dummy_20240101000000.zip").exists());
+        assert!(backup_root_path.join("dummy_20240601000000.zip").exists());
+        assert!(backup_root_path.join("dummy_20250101000000.zip").exists());
+    }
 }